@@ -0,0 +1,396 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+// The core interpreter only needs `Read`/`Write` and a `Vec`. With `std` it
+// draws them from the standard library and `alloc` supplies the instruction
+// vector otherwise, so the crate can run on a bare-metal target with the host
+// supplying memory and I/O. Two no_std sources for the traits are offered: the
+// external `core_io` crate (opt-in via the `core_io` feature) or a minimal
+// built-in shim the host implements for its own byte source and sink.
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+#[cfg(all(not(feature = "std"), feature = "core_io"))]
+use core_io as io;
+#[cfg(all(not(feature = "std"), feature = "core_io"))]
+use core_io::{Read, Write};
+
+#[cfg(all(not(feature = "std"), not(feature = "core_io")))]
+use shim as io;
+#[cfg(all(not(feature = "std"), not(feature = "core_io")))]
+pub use shim::{Read, Write};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+// A core_io-style shim used when the crate is built no_std without the
+// external core_io dependency. The host implements these traits for whatever
+// byte source and sink it has.
+#[cfg(all(not(feature = "std"), not(feature = "core_io")))]
+pub mod shim {
+    #[derive(Debug)]
+    pub struct Error;
+
+    pub trait Read {
+        fn read_exact(&mut self, buf : &mut [u8]) -> Result<(), Error>;
+    }
+
+    pub trait Write {
+        fn write_all(&mut self, buf : &[u8]) -> Result<(), Error>;
+    }
+}
+
+pub trait Cell : Copy {
+    fn zero() -> Self;
+    fn is_zero(&self) -> bool;
+    fn checked_add_delta(self, delta : i32) -> Option<Self> where Self: Sized;
+    fn wrapping_add_delta(self, delta : i32) -> Self;
+    fn from_input_byte(byte : u8) -> Self;
+    fn to_output_byte(self) -> u8;
+}
+
+macro_rules! impl_cell {
+    ($t:ty) => {
+        impl Cell for $t {
+            fn zero() -> Self { 0 }
+            fn is_zero(&self) -> bool { *self == 0 }
+            fn checked_add_delta(self, delta : i32) -> Option<Self> {
+                let result = self as i64 + delta as i64;
+                if result < 0 || result > <$t>::MAX as i64 {
+                    None
+                } else {
+                    Some(result as $t)
+                }
+            }
+            fn wrapping_add_delta(self, delta : i32) -> Self { self.wrapping_add(delta as $t) }
+            fn from_input_byte(byte : u8) -> Self { byte as $t }
+            fn to_output_byte(self) -> u8 { self as u8 }
+        }
+    };
+}
+
+impl_cell!(u8);
+impl_cell!(u16);
+impl_cell!(u32);
+
+#[derive(Clone, Copy, Debug)]
+pub enum ArithmeticMode {
+    Checked,
+    Wrapping,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum PointerMode {
+    Checked,
+    Wrapping,
+}
+
+pub struct InterpreterContext<'a, C, R, W>  {
+    memory : &'a mut [C],
+    mem_cell_index : usize,
+    script_cursor : usize,
+    arithmetic : ArithmeticMode,
+    pointer : PointerMode,
+    input : R,
+    output : W,
+}
+
+#[derive(Debug)]
+pub struct MemCellRelatedError {
+    pub cell_index: usize,
+    pub script_pos: usize,
+}
+
+#[derive(Debug)]
+pub struct PositionalError {
+    pub script_pos : usize,
+}
+
+#[derive(Debug)]
+pub struct IOErrorInfo {
+    pub script_pos : usize,
+    pub source : io::Error,
+}
+
+#[derive(Debug)]
+pub enum ExecutionError{
+    MemCellAccessOutOfBounds(MemCellRelatedError),
+    MemCellValueOverflow(MemCellRelatedError),
+    MemCellValueUnderflow(MemCellRelatedError),
+    UnexpectedCycleEnd(PositionalError),
+    IOError(IOErrorInfo),
+}
+
+impl ExecutionError {
+    pub fn script_pos(&self) -> usize {
+        match self {
+            ExecutionError::MemCellAccessOutOfBounds(e)
+            | ExecutionError::MemCellValueOverflow(e)
+            | ExecutionError::MemCellValueUnderflow(e) => e.script_pos,
+            ExecutionError::UnexpectedCycleEnd(e) => e.script_pos,
+            ExecutionError::IOError(e) => e.script_pos,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum OpKind {
+    Add(i32),
+    Move(i32),
+    Output,
+    Input,
+    SetZero,
+    ScanZero(i32),
+    LoopStart(usize),
+    LoopEnd(usize),
+}
+
+#[derive(Clone, Copy)]
+struct Op {
+    kind : OpKind,
+    src_pos : usize,
+}
+
+impl<'a, C: Cell, R: Read, W: Write> InterpreterContext<'a, C, R, W> {
+    pub fn new(tape : &'a mut [C], arithmetic : ArithmeticMode, pointer : PointerMode, input : R, output : W) -> InterpreterContext<'a, C, R, W> {
+        InterpreterContext {
+            memory : tape,
+            mem_cell_index : 0,
+            script_cursor : 0,
+            arithmetic,
+            pointer,
+            input,
+            output,
+        }
+    }
+
+    fn mem_cell_err(&self) -> MemCellRelatedError {
+        MemCellRelatedError{
+            cell_index: self.mem_cell_index,
+            script_pos: self.script_cursor,
+        }
+    }
+
+    fn io_err(&self, source : io::Error) -> IOErrorInfo {
+        IOErrorInfo{
+            script_pos: self.script_cursor,
+            source,
+        }
+    }
+
+    fn check_out_of_bounds_access(&self) -> Result<(), ExecutionError> {
+        if self.mem_cell_index >= self.memory.len() {
+            return Err(ExecutionError::MemCellAccessOutOfBounds(self.mem_cell_err()));
+        }
+        Ok(())
+    }
+
+    fn move_by(&mut self, delta : i32) -> Result<(), ExecutionError> {
+        let new_index = self.mem_cell_index as i64 + delta as i64;
+        match self.pointer {
+            PointerMode::Checked => {
+                if new_index < 0 {
+                    return Err(ExecutionError::MemCellAccessOutOfBounds(self.mem_cell_err()));
+                }
+                // An index past the end is caught by the access checks so the
+                // error still points at the offending op.
+                self.mem_cell_index = new_index as usize;
+            }
+            PointerMode::Wrapping => {
+                let len = self.memory.len() as i64;
+                self.mem_cell_index = (((new_index % len) + len) % len) as usize;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_add(&mut self, delta : i32) -> Result<(), ExecutionError> {
+        self.check_out_of_bounds_access()?;
+        let mode = self.arithmetic;
+        let cur_cell = &mut self.memory[self.mem_cell_index];
+        match mode {
+            ArithmeticMode::Wrapping => *cur_cell = cur_cell.wrapping_add_delta(delta),
+            ArithmeticMode::Checked => match cur_cell.checked_add_delta(delta) {
+                Some(v) => *cur_cell = v,
+                None => {
+                    let err = self.mem_cell_err();
+                    return Err(if delta >= 0 {
+                        ExecutionError::MemCellValueOverflow(err)
+                    } else {
+                        ExecutionError::MemCellValueUnderflow(err)
+                    });
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn set_zero(&mut self) -> Result<(), ExecutionError> {
+        self.check_out_of_bounds_access()?;
+        self.memory[self.mem_cell_index] = C::zero();
+        Ok(())
+    }
+
+    fn scan_zero(&mut self, dir : i32) -> Result<(), ExecutionError> {
+        loop {
+            self.check_out_of_bounds_access()?;
+            if self.memory[self.mem_cell_index].is_zero() {
+                return Ok(());
+            }
+            self.move_by(dir)?;
+        }
+    }
+
+    fn put_checked(&mut self) -> Result<(), ExecutionError> {
+        self.check_out_of_bounds_access()?;
+        let byte = self.memory[self.mem_cell_index].to_output_byte();
+        match self.output.write_all(&[byte]) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(ExecutionError::IOError(self.io_err(e))),
+        }
+    }
+
+    fn get_checked(&mut self) -> Result<(), ExecutionError> {
+        self.check_out_of_bounds_access()?;
+        let mut buf = [0u8; 1];
+        match self.input.read_exact(&mut buf) {
+            Ok(()) => {
+                self.memory[self.mem_cell_index] = C::from_input_byte(buf[0]);
+                Ok(())
+            }
+            Err(e) => Err(ExecutionError::IOError(self.io_err(e))),
+        }
+    }
+
+    pub fn execute(&mut self, script : &str) -> Result<(), ExecutionError>{
+        let ops = compile(script, self.arithmetic, self.pointer)?;
+        let mut op_cursor = 0;
+        while op_cursor < ops.len() {
+            let op = ops[op_cursor];
+            // Keep script_cursor on the source offset the op came from so any
+            // error still reports the original program position.
+            self.script_cursor = op.src_pos;
+            match op.kind {
+                OpKind::Add(delta) => self.apply_add(delta)?,
+                OpKind::Move(delta) => self.move_by(delta)?,
+                OpKind::Output => self.put_checked()?,
+                OpKind::Input => self.get_checked()?,
+                OpKind::SetZero => self.set_zero()?,
+                OpKind::ScanZero(dir) => self.scan_zero(dir)?,
+                OpKind::LoopStart(close) => {
+                    self.check_out_of_bounds_access()?;
+                    if self.memory[self.mem_cell_index].is_zero() {
+                        op_cursor = close;
+                    }
+                },
+                OpKind::LoopEnd(open) => {
+                    self.check_out_of_bounds_access()?;
+                    if !self.memory[self.mem_cell_index].is_zero() {
+                        op_cursor = open;
+                    }
+                },
+            }
+            op_cursor += 1;
+        }
+        Ok(())
+    }
+
+    pub fn execute_from_start(&mut self, script : &str) -> Result<(), ExecutionError> {
+        self.script_cursor = 0;
+        self.execute(script)
+    }
+}
+
+// Compile raw source into a compressed instruction vector: runs of +/- and
+// >/< are folded into a single Add/Move, the clear-loop [-] (and [+] under
+// wrapping arithmetic) becomes SetZero and the scan-loops [>]/[<] become
+// ScanZero, and the bracket pairs are resolved into jumps between op indices.
+fn compile(script : &str, arithmetic : ArithmeticMode, pointer : PointerMode) -> Result<Vec<Op>, ExecutionError> {
+    let bytes = script.as_bytes();
+    let mut ops : Vec<Op> = Vec::new();
+    let mut open_stack : Vec<usize> = Vec::new();
+    // In wrapping modes the net delta is all that matters, so whole runs fold.
+    // In checked modes an intermediate over/underflow (or pointer step out of
+    // bounds) must still be reported, and that information survives folding
+    // only within a same-sign run, so we stop folding at every sign change.
+    let fold_mixed_arith = matches!(arithmetic, ArithmeticMode::Wrapping);
+    let fold_mixed_ptr = matches!(pointer, PointerMode::Wrapping);
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'+' | b'-' => {
+                let start = pos;
+                let mut delta : i32 = 0;
+                while pos < bytes.len() && (bytes[pos] == b'+' || bytes[pos] == b'-') {
+                    let step = if bytes[pos] == b'+' { 1 } else { -1 };
+                    if !fold_mixed_arith && delta != 0 && (step > 0) != (delta > 0) {
+                        break;
+                    }
+                    delta += step;
+                    pos += 1;
+                }
+                ops.push(Op{ kind: OpKind::Add(delta), src_pos: start });
+            }
+            b'>' | b'<' => {
+                let start = pos;
+                let mut delta : i32 = 0;
+                while pos < bytes.len() && (bytes[pos] == b'>' || bytes[pos] == b'<') {
+                    let step = if bytes[pos] == b'>' { 1 } else { -1 };
+                    if !fold_mixed_ptr && delta != 0 && (step > 0) != (delta > 0) {
+                        break;
+                    }
+                    delta += step;
+                    pos += 1;
+                }
+                ops.push(Op{ kind: OpKind::Move(delta), src_pos: start });
+            }
+            b'.' => {
+                ops.push(Op{ kind: OpKind::Output, src_pos: pos });
+                pos += 1;
+            }
+            b',' => {
+                ops.push(Op{ kind: OpKind::Input, src_pos: pos });
+                pos += 1;
+            }
+            // [-] clears the cell in every mode. [+] only clears under
+            // wrapping arithmetic; under checked arithmetic it must run until
+            // it overflows, so it falls through to a regular loop.
+            b'[' if pos + 2 < bytes.len() && bytes[pos + 2] == b']'
+                && (bytes[pos + 1] == b'-'
+                    || (bytes[pos + 1] == b'+' && matches!(arithmetic, ArithmeticMode::Wrapping))) => {
+                ops.push(Op{ kind: OpKind::SetZero, src_pos: pos });
+                pos += 3;
+            }
+            b'[' if pos + 2 < bytes.len() && bytes[pos + 2] == b']'
+                && (bytes[pos + 1] == b'>' || bytes[pos + 1] == b'<') => {
+                let dir = if bytes[pos + 1] == b'>' { 1 } else { -1 };
+                ops.push(Op{ kind: OpKind::ScanZero(dir), src_pos: pos });
+                pos += 3;
+            }
+            b'[' => {
+                open_stack.push(ops.len());
+                ops.push(Op{ kind: OpKind::LoopStart(0), src_pos: pos });
+                pos += 1;
+            }
+            b']' => {
+                let open = match open_stack.pop() {
+                    Some(open) => open,
+                    None => return Err(ExecutionError::UnexpectedCycleEnd(PositionalError{ script_pos: pos })),
+                };
+                let close = ops.len();
+                ops[open].kind = OpKind::LoopStart(close);
+                ops.push(Op{ kind: OpKind::LoopEnd(open), src_pos: pos });
+                pos += 1;
+            }
+            _ => pos += 1,
+        }
+    }
+    if let Some(&open) = open_stack.last() {
+        return Err(ExecutionError::UnexpectedCycleEnd(PositionalError{ script_pos: ops[open].src_pos }));
+    }
+    Ok(ops)
+}
@@ -2,182 +2,169 @@ use std::io;
 use std::io::Read;
 use std::fs;
 use std::env;
-use std::result::Result;
 
-const MAX_MEM_CELL_COUNT : usize = 1024;
-
-struct InterpreterContext  {
-    memory : [i8; MAX_MEM_CELL_COUNT],
-    mem_cell_index : usize,
-    cycle_stack : Vec<usize>,
-    script_cursor : usize,
-}
+use rbfi::{ArithmeticMode, Cell, ExecutionError, InterpreterContext, PointerMode};
 
-#[derive(Debug)]
-struct MemCellRelatedError {
-    cell_index: usize,
-    script_pos: usize,
-}
+const MAX_MEM_CELL_COUNT : usize = 1024;
 
-#[derive(Debug)]
-struct PositionalError {
-    script_pos : usize,
+#[derive(Clone, Copy, Debug)]
+enum CellType {
+    U8,
+    U16,
+    U32,
 }
 
-enum ExecutionError{
-    MemCellAccessOutOfBounds(MemCellRelatedError),
-    MemCellValueOverflow(MemCellRelatedError),
-    MemCellValueUnderflow(MemCellRelatedError),
-    UnexpectedCycleEnd(PositionalError),
-    IOError(PositionalError),
+struct Config {
+    tape_len : usize,
+    cell_type : CellType,
+    arithmetic : ArithmeticMode,
+    pointer : PointerMode,
 }
 
-impl InterpreterContext {
-    fn new() -> InterpreterContext {
-        InterpreterContext {
-            memory : [0; MAX_MEM_CELL_COUNT],
-            mem_cell_index : 0,
-            cycle_stack : Vec::new(),
-            script_cursor : 0,
-        }
-    }
-
-    fn mem_cell_err(&self) -> MemCellRelatedError {
-        MemCellRelatedError{
-            cell_index: self.mem_cell_index,
-            script_pos: self.script_cursor,
+impl Config {
+    fn default() -> Config {
+        Config {
+            tape_len : MAX_MEM_CELL_COUNT,
+            cell_type : CellType::U8,
+            arithmetic : ArithmeticMode::Checked,
+            pointer : PointerMode::Checked,
         }
     }
+}
 
-    fn pos_err(&self) -> PositionalError {
-        PositionalError{
-            script_pos: self.script_cursor,
+fn parse_config(args : &[String]) -> Result<(String, Config), String> {
+    let mut config = Config::default();
+    let mut filename : Option<String> = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--tape-len" => {
+                let value = iter.next().ok_or_else(|| "Expected a value after --tape-len".to_string())?;
+                config.tape_len = value.parse().map_err(|_| format!("Invalid tape length: {}", value))?;
+                if config.tape_len == 0 {
+                    return Err("Tape length must be at least 1".to_string());
+                }
+            }
+            "--cell" => {
+                let value = iter.next().ok_or_else(|| "Expected a value after --cell".to_string())?;
+                config.cell_type = match value.as_str() {
+                    "u8" => CellType::U8,
+                    "u16" => CellType::U16,
+                    "u32" => CellType::U32,
+                    other => return Err(format!("Unknown cell type: {} (expected u8, u16 or u32)", other)),
+                };
+            }
+            "--arith" => {
+                let value = iter.next().ok_or_else(|| "Expected a value after --arith".to_string())?;
+                config.arithmetic = match value.as_str() {
+                    "checked" => ArithmeticMode::Checked,
+                    "wrapping" => ArithmeticMode::Wrapping,
+                    other => return Err(format!("Unknown arithmetic mode: {} (expected checked or wrapping)", other)),
+                };
+            }
+            "--pointer" => {
+                let value = iter.next().ok_or_else(|| "Expected a value after --pointer".to_string())?;
+                config.pointer = match value.as_str() {
+                    "checked" => PointerMode::Checked,
+                    "wrapping" => PointerMode::Wrapping,
+                    other => return Err(format!("Unknown pointer mode: {} (expected checked or wrapping)", other)),
+                };
+            }
+            other => {
+                if filename.is_some() {
+                    return Err(format!("Unexpected argument: {}", other));
+                }
+                filename = Some(other.to_string());
+            }
         }
     }
-
-    fn check_out_of_bounds_access(&self) -> Result<(), ExecutionError> {
-        if self.mem_cell_index >= MAX_MEM_CELL_COUNT {
-            return Err(ExecutionError::MemCellAccessOutOfBounds(self.mem_cell_err()));
-        }
-        Ok(())
+    match filename {
+        Some(name) => Ok((name, config)),
+        None => Err("Expected to get a script filename as an argument!".to_string()),
     }
+}
 
-    fn inc_checked(&mut self) -> Result<(), ExecutionError> {
-        self.check_out_of_bounds_access()?;
-        let cur_cell = &mut self.memory[self.mem_cell_index];
-        match cur_cell.checked_add(1) {
-            Some(v) => *cur_cell = v,
-            None => return Err(ExecutionError::MemCellValueOverflow(self.mem_cell_err()))
-        }
-        Ok(())
-    }
-    
-    fn dec_checked(&mut self) -> Result<(), ExecutionError> {
-        self.check_out_of_bounds_access()?;
-        let cur_cell = &mut self.memory[self.mem_cell_index];
-        match cur_cell.checked_sub(1) {
-            Some(v) => *cur_cell = v,
-            None => return Err(ExecutionError::MemCellValueUnderflow(self.mem_cell_err()))
-        }
-        Ok(())
-    }
-    
-    fn put_checked(&self) -> Result<(), ExecutionError> {
-        self.check_out_of_bounds_access()?;
-        print!("{}", self.memory[self.mem_cell_index] as u8 as char);
-        Ok(())
+fn describe(err : &ExecutionError) -> String {
+    match err {
+        ExecutionError::MemCellAccessOutOfBounds(e) => format!("memory cell access out of bounds (cell {})", e.cell_index),
+        ExecutionError::MemCellValueOverflow(e) => format!("cell value overflow (cell {})", e.cell_index),
+        ExecutionError::MemCellValueUnderflow(e) => format!("cell value underflow (cell {})", e.cell_index),
+        ExecutionError::UnexpectedCycleEnd(_) => "unmatched loop bracket".to_string(),
+        ExecutionError::IOError(e) => format!("I/O error: {}", e.source),
     }
+}
 
-    fn get_checked(&mut self) -> Result<(), ExecutionError> {
-        self.check_out_of_bounds_access()?;   
-        match get_one_byte_from_stdin() {
-            Some(c) => self.memory[self.mem_cell_index] = c,
-            None => return Err(ExecutionError::IOError(self.pos_err()))
+// Byte offsets at which each source line begins, computed once so a
+// diagnostic can turn a raw cursor into a line/column without rescanning the
+// whole program every time.
+fn compute_line_starts(script : &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    for (index, byte) in script.bytes().enumerate() {
+        if byte == b'\n' {
+            starts.push(index + 1);
         }
-        Ok(())
-    }
-    
-    fn execute(&mut self, script : &str) -> Result<(), ExecutionError>{
-        loop {
-            match script.as_bytes()[self.script_cursor] {
-                b'>' => self.mem_cell_index += 1,
-                b'<' => self.mem_cell_index -= 1,
-                b'+' => self.inc_checked()?,
-                b'-' => self.dec_checked()?,
-                b'.' => self.put_checked()?,
-                b',' => self.get_checked()?,
-                b'[' => {
-                    self.check_out_of_bounds_access()?;
-                    if self.memory[self.mem_cell_index] != 0 {
-                        self.cycle_stack.push(self.script_cursor);
-                    } else {
-                        match script.split_at(self.script_cursor).1.find(']') {
-                            Some(offset) => self.script_cursor += offset,
-                            None => self.script_cursor = script.len(),
-                        }
-                    }
-                },
-                b']' => {
-                    if !self.cycle_stack.is_empty() {
-                        self.script_cursor = self.cycle_stack.pop().unwrap() - 1;
-                    } else {
-                        return Err(ExecutionError::UnexpectedCycleEnd(self.pos_err()));
-                    }
-                },
-                _ => {}
-            }
-    
-            self.script_cursor += 1;
-            if self.script_cursor >= script.len() {
-                break;
-            }
-        }
-        Ok(())
     }
+    starts
+}
 
-    fn execute_from_start(&mut self, script : &str) -> Result<(), ExecutionError> {
-        self.script_cursor = 0;
-        self.execute(script)
-    }
+fn report_error(err : &ExecutionError, script : &str, line_starts : &[usize]) {
+    let pos = err.script_pos();
+    let line_index = match line_starts.binary_search(&pos) {
+        Ok(index) => index,
+        Err(index) => index - 1,
+    };
+    let line_start = line_starts[line_index];
+    let line_end = script[line_start..].find('\n').map_or(script.len(), |offset| line_start + offset);
+    let line_text = &script[line_start..line_end];
+    let column = pos - line_start;
+    println!("error: {} at line {}, col {}", describe(err), line_index + 1, column + 1);
+    println!("{}", line_text);
+    println!("{}^", " ".repeat(column));
 }
 
-fn get_one_byte_from_stdin() -> Option<i8> {
-    io::stdin()
-        .bytes()
-        .next()
-        .and_then(|result| result.ok())
-        .map(|byte| byte as i8)
+fn run<C: Cell>(config : &Config, script : &str) -> Result<(), ExecutionError> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut tape = vec![C::zero(); config.tape_len];
+    let mut context = InterpreterContext::<C, _, _>::new(&mut tape, config.arithmetic, config.pointer, stdin.lock(), stdout.lock());
+    context.execute_from_start(script)
 }
 
 fn main() {
-    match env::args().nth(1) {
-        Some(arg) => {
-            let script_str = {
-                let script_file = match fs::File::open(arg.as_str()) {
-                    Ok(file) => file,
-                    Err(e) => {
-                        println!("Couldn't open file {}, error: {}", arg, e);
-                        return;
-                    }
-                };
-                let mut reader = io::BufReader::new(script_file);
-                let mut content = String::new();
-                reader.read_to_string(&mut content).unwrap();
-                content
-            };
+    let args : Vec<String> = env::args().skip(1).collect();
+    let (filename, config) = match parse_config(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
 
-            let mut context = InterpreterContext::new();
-            match context.execute_from_start(script_str.as_str()) {
-                Ok(()) => println!("Executted successfully. Exitting..."),
-                Err(e) => match e {
-                    ExecutionError::MemCellAccessOutOfBounds(err) => println!("MemCellAccessOutOfBounds Error. {:?}", err),
-                    ExecutionError::MemCellValueOverflow(err) => println!("MemCellValueOverflow Error. {:?}", err),
-                    ExecutionError::MemCellValueUnderflow(err) => println!("MemCellValueUnderflow Error. {:?}", err),
-                    ExecutionError::UnexpectedCycleEnd(err) => println!("UnexpectedCycleEnd Error. {:?}", err),
-                    ExecutionError::IOError(err) => println!("IOError Error. Couldn't read from the terminal. {:?}", err),
-                }
+    let script_str = {
+        let script_file = match fs::File::open(filename.as_str()) {
+            Ok(file) => file,
+            Err(e) => {
+                println!("Couldn't open file {}, error: {}", filename, e);
+                return;
             }
+        };
+        let mut reader = io::BufReader::new(script_file);
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        content
+    };
+
+    let result = match config.cell_type {
+        CellType::U8 => run::<u8>(&config, script_str.as_str()),
+        CellType::U16 => run::<u16>(&config, script_str.as_str()),
+        CellType::U32 => run::<u32>(&config, script_str.as_str()),
+    };
+
+    match result {
+        Ok(()) => println!("Executted successfully. Exitting..."),
+        Err(e) => {
+            let line_starts = compute_line_starts(script_str.as_str());
+            report_error(&e, script_str.as_str(), &line_starts);
         }
-        None => println!("Error: Expected to get a script filename as an argument!")
     }
 }
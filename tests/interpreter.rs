@@ -0,0 +1,80 @@
+use rbfi::{ArithmeticMode, ExecutionError, InterpreterContext, PointerMode};
+
+// Drive a script over an in-memory reader/writer so its output can be asserted
+// deterministically — the whole point of the Read/Write seam.
+fn run(script : &str, input : &[u8], tape_len : usize, arithmetic : ArithmeticMode, pointer : PointerMode)
+    -> Result<Vec<u8>, ExecutionError> {
+    let mut tape = vec![0u8; tape_len];
+    let mut output = Vec::new();
+    {
+        let mut context = InterpreterContext::<u8, _, _>::new(&mut tape, arithmetic, pointer, input, &mut output);
+        context.execute_from_start(script)?;
+    }
+    Ok(output)
+}
+
+fn run_default(script : &str, input : &[u8]) -> Result<Vec<u8>, ExecutionError> {
+    run(script, input, 1024, ArithmeticMode::Checked, PointerMode::Checked)
+}
+
+#[test]
+fn hello_world_output_is_captured() {
+    let hello = "++++++++[>++++[>++>+++>+++>+<<<<-]>+>+>->>+[<]<-]>>.>---.+++++++..+++.>>.<-.<.+++.------.--------.>>+.>++.";
+    let output = run_default(hello, b"").unwrap();
+    assert_eq!(output, b"Hello World!\n");
+}
+
+#[test]
+fn single_loop_multiplies() {
+    // +++ in cell 0, then [>+++<-] adds 3 to cell 1 for each, leaving 9.
+    let output = run_default("+++[>+++<-]>.", b"").unwrap();
+    assert_eq!(output, vec![9]);
+}
+
+#[test]
+fn input_is_read_from_the_reader() {
+    // Read a byte, increment it, echo it back.
+    let output = run_default(",+.", b"A").unwrap();
+    assert_eq!(output, b"B");
+}
+
+#[test]
+fn clear_loop_plus_overflows_under_checked() {
+    let result = run_default("+[+].", b"");
+    assert!(matches!(result, Err(ExecutionError::MemCellValueOverflow(_))));
+}
+
+#[test]
+fn clear_loop_plus_clears_under_wrapping() {
+    let output = run("+[+].", b"", 1024, ArithmeticMode::Wrapping, PointerMode::Checked).unwrap();
+    assert_eq!(output, vec![0]);
+}
+
+#[test]
+fn checked_arithmetic_detects_mixed_sign_overflow() {
+    // 255 increments leave the cell at its max; the extra `+` in `+-` must
+    // overflow even though the net delta is zero.
+    let mut program = "+".repeat(255);
+    program.push_str("+-");
+    let result = run_default(&program, b"");
+    assert!(matches!(result, Err(ExecutionError::MemCellValueOverflow(_))));
+}
+
+#[test]
+fn pointer_underflow_errors_under_checked() {
+    let result = run_default("<", b"");
+    assert!(matches!(result, Err(ExecutionError::MemCellAccessOutOfBounds(_))));
+}
+
+#[test]
+fn pointer_wraps_to_end_under_wrapping() {
+    // On a 3-cell tape, `<` from index 0 wraps to index 2 (which is zero).
+    let output = run("<.", b"", 3, ArithmeticMode::Checked, PointerMode::Wrapping).unwrap();
+    assert_eq!(output, vec![0]);
+}
+
+#[test]
+fn unmatched_bracket_is_rejected() {
+    let result = run_default("[", b"");
+    assert!(matches!(result, Err(ExecutionError::UnexpectedCycleEnd(_))));
+}